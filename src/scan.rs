@@ -1,15 +1,283 @@
-use crate::wasm_rxing::{convert_js_image_to_luma, decode_barcode};
+use crate::wasm_rxing::{convert_js_image_to_luma, decode_barcode, decode_barcodes};
 use gloo::timers::callback::Interval;
 use gloo::utils::errors::JsError;
 use gloo::utils::window;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
 use web_sys::{
-    CanvasRenderingContext2d, HtmlCanvasElement, HtmlVideoElement, MediaStream,
+    CanvasRenderingContext2d, File, HtmlCanvasElement, HtmlImageElement, HtmlInputElement,
+    HtmlSelectElement, HtmlVideoElement, MediaDeviceInfo, MediaDeviceKind, MediaStream,
     MediaStreamConstraints, MediaStreamTrack, MediaTrackConstraints, VideoFacingModeEnum,
 };
 use yew::prelude::*;
 
+/// Convert an arbitrary rejected `JsValue` into a `JsError`, falling back to a
+/// generic message when the value isn't an `Error` instance (e.g. a DOM
+/// `Event` or a `DOMException`, neither of which `JsError::try_from` accepts).
+fn js_error_from_value(value: JsValue) -> JsError {
+    JsError::try_from(value)
+        .unwrap_or_else(|_| JsError::from(js_sys::Error::new("an unknown error occurred")))
+}
+
+/// A video input device reported by `MediaDevices::enumerate_devices()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraInfo {
+    pub device_id: String,
+    pub label: String,
+}
+
+/// List the available video input devices the browser can see.
+///
+/// Enumerates every media device reported by `MediaDevices::enumerate_devices()`
+/// and keeps only the ones of kind `videoinput`.
+async fn enumerate_cameras() -> Result<Vec<CameraInfo>, JsValue> {
+    let media_devices = window().navigator().media_devices()?;
+    let devices = wasm_bindgen_futures::JsFuture::from(media_devices.enumerate_devices()?).await?;
+    let devices: js_sys::Array = devices.unchecked_into();
+    Ok(devices
+        .iter()
+        .filter_map(|device| device.dyn_into::<MediaDeviceInfo>().ok())
+        .filter(|device| device.kind() == MediaDeviceKind::Videoinput)
+        .map(|device| CameraInfo {
+            device_id: device.device_id(),
+            label: device.label(),
+        })
+        .collect())
+}
+
+/// Load an `HtmlImageElement` from a user-supplied `File` (e.g. a gallery photo),
+/// resolving once the browser has finished decoding it.
+async fn load_image_from_file(file: &File) -> Result<HtmlImageElement, JsValue> {
+    let url = web_sys::Url::create_object_url_with_blob(file)?;
+    let image = HtmlImageElement::new()?;
+    image.set_src(&url);
+
+    let result_image = image.clone();
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let onload = Closure::once_into_js(move || {
+            let _ = resolve.call0(&JsValue::NULL);
+        });
+        image.set_onload(Some(onload.unchecked_ref()));
+
+        let onerror = Closure::once_into_js(move |event: JsValue| {
+            let _ = reject.call1(&JsValue::NULL, &event);
+        });
+        image.set_onerror(Some(onerror.unchecked_ref()));
+    });
+
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(result_image)
+}
+
+/// A sub-rectangle of the captured frame to decode, expressed as fractions of
+/// the frame's width/height so it doesn't depend on the camera's resolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScanRegion {
+    Normalized {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    /// A centered region whose width is `fraction` of the frame's width and
+    /// whose height is `fraction` of the frame's height; it covers a square of
+    /// pixels only when the frame itself is square.
+    CenteredSquare {
+        fraction: f64,
+    },
+}
+
+impl ScanRegion {
+    fn normalized(&self) -> (f64, f64, f64, f64) {
+        match *self {
+            ScanRegion::Normalized {
+                x,
+                y,
+                width,
+                height,
+            } => (x, y, width, height),
+            ScanRegion::CenteredSquare { fraction } => {
+                let side = fraction.clamp(0.0, 1.0);
+                ((1.0 - side) / 2.0, (1.0 - side) / 2.0, side, side)
+            }
+        }
+    }
+
+    /// Like [`Self::normalized`], but clamped so the fractions always describe
+    /// a rectangle that fits within the frame (no negative origin, no overflow
+    /// past the right/bottom edge).
+    fn clamped_normalized(&self) -> (f64, f64, f64, f64) {
+        let (nx, ny, nw, nh) = self.normalized();
+        let x = nx.clamp(0.0, 1.0);
+        let y = ny.clamp(0.0, 1.0);
+        let w = nw.clamp(0.0, 1.0).min(1.0 - x);
+        let h = nh.clamp(0.0, 1.0).min(1.0 - y);
+        (x, y, w, h)
+    }
+
+    /// Resolve this region to a pixel rectangle, clamped to the given frame size.
+    fn to_pixel_rect(&self, video_width: u32, video_height: u32) -> (f64, f64, f64, f64) {
+        let (nx, ny, nw, nh) = self.clamped_normalized();
+        let width = video_width as f64;
+        let height = video_height as f64;
+        (nx * width, ny * height, nw * width, nh * height)
+    }
+}
+
+/// The zoom range a camera's track reports via `MediaTrackCapabilities`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoomRange {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+/// Inspect a video track's `MediaTrackCapabilities` for the non-standard
+/// `torch` and `zoom` capabilities. These aren't part of `web_sys`'s typed
+/// bindings, so they're read off the raw capabilities object with
+/// `js_sys::Reflect`.
+fn read_camera_capabilities(track: &MediaStreamTrack) -> (bool, Option<ZoomRange>) {
+    let capabilities: JsValue = track.get_capabilities().into();
+
+    let has_torch = js_sys::Reflect::has(&capabilities, &JsValue::from_str("torch"))
+        .unwrap_or(false);
+
+    let zoom = js_sys::Reflect::get(&capabilities, &JsValue::from_str("zoom"))
+        .ok()
+        .filter(|value| !value.is_undefined())
+        .and_then(|zoom_capability| {
+            let min = js_sys::Reflect::get(&zoom_capability, &JsValue::from_str("min"))
+                .ok()?
+                .as_f64()?;
+            let max = js_sys::Reflect::get(&zoom_capability, &JsValue::from_str("max"))
+                .ok()?
+                .as_f64()?;
+            let step = js_sys::Reflect::get(&zoom_capability, &JsValue::from_str("step"))
+                .ok()?
+                .as_f64()?;
+            Some(ZoomRange { min, max, step })
+        });
+
+    (has_torch, zoom)
+}
+
+/// Apply a single advanced constraint (e.g. `torch` or `zoom`) to a stream's
+/// first video track and wait for the browser to accept it.
+async fn apply_advanced_constraint(
+    stream: &MediaStream,
+    key: &str,
+    value: JsValue,
+) -> Result<(), JsValue> {
+    let track = stream
+        .get_video_tracks()
+        .get(0)
+        .dyn_into::<MediaStreamTrack>()?;
+
+    let constraint = js_sys::Object::new();
+    js_sys::Reflect::set(&constraint, &JsValue::from_str(key), &value)?;
+    let advanced_constraints = js_sys::Array::new();
+    advanced_constraints.push(&constraint);
+    let mut video_constraints = MediaTrackConstraints::new();
+    video_constraints.advanced(&advanced_constraints);
+
+    let promise = track.apply_constraints_with_constraints(&video_constraints)?;
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Request a camera stream, optionally pinned to a specific `deviceId`
+/// instead of the default `facingMode: environment`.
+async fn start_camera(device_id: Option<String>) -> ScannerMessage {
+    let mut constraints = MediaStreamConstraints::new();
+    let mut video_constraints = MediaTrackConstraints::new();
+
+    let advanced_constraints = js_sys::Array::new();
+    let torch_constraint = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &torch_constraint,
+        &JsValue::from_str("torch"),
+        &JsValue::from_bool(false),
+    )
+    .unwrap();
+    advanced_constraints.push(&torch_constraint);
+    video_constraints.advanced(&advanced_constraints);
+
+    if let Some(device_id) = device_id {
+        let device_id_constraint = js_sys::Object::new();
+        js_sys::Reflect::set(
+            &device_id_constraint,
+            &JsValue::from_str("exact"),
+            &JsValue::from_str(&device_id),
+        )
+        .unwrap();
+        video_constraints.device_id(&device_id_constraint.into());
+    } else {
+        video_constraints.facing_mode(&VideoFacingModeEnum::Environment.into());
+    }
+    video_constraints.frame_rate(&10.into());
+
+    constraints.video(&video_constraints);
+    match window().navigator().media_devices() {
+        Ok(devs) => match devs.get_user_media_with_constraints(&constraints) {
+            Ok(promise) => match wasm_bindgen_futures::JsFuture::from(promise).await {
+                Ok(stream) => ScannerMessage::ReceivedStream(stream.unchecked_into()),
+                Err(e) => ScannerMessage::Error(js_error_from_value(e)),
+            },
+            Err(e) => ScannerMessage::Error(js_error_from_value(e)),
+        },
+        Err(e) => ScannerMessage::Error(js_error_from_value(e)),
+    }
+}
+
+/// Decode a barcode out of a still image file, the same way a captured video
+/// frame is decoded: draw it to the hidden canvas, convert to luma, then run
+/// it through rxing.
+#[allow(clippy::too_many_arguments)]
+async fn decode_image_file(
+    file: File,
+    canvas: HtmlCanvasElement,
+    try_harder: Option<bool>,
+    filter_image: Option<bool>,
+    formats: Vec<rxing::BarcodeFormat>,
+    pure_barcode: Option<bool>,
+) -> Result<rxing::RXingResult, JsError> {
+    let image = load_image_from_file(&file)
+        .await
+        .map_err(js_error_from_value)?;
+
+    let width = image.natural_width();
+    let height = image.natural_height();
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let context = canvas
+        .get_context("2d")
+        .map_err(js_error_from_value)?
+        .expect("context should be available")
+        .unchecked_into::<CanvasRenderingContext2d>();
+
+    context
+        .draw_image_with_html_image_element(&image, 0.0, 0.0)
+        .map_err(js_error_from_value)?;
+
+    let image_data = context
+        .get_image_data(0.0, 0.0, width as f64, height as f64)
+        .map_err(js_error_from_value)?;
+
+    decode_barcode(
+        convert_js_image_to_luma(image_data.data().as_ref()),
+        image_data.width(),
+        image_data.height(),
+        try_harder,
+        filter_image,
+        &formats,
+        pure_barcode,
+    )
+    .map_err(|e| JsError::from(js_sys::Error::new(e.to_string().as_str())))
+}
+
 pub struct Scanner {
     video_ref: NodeRef,
     canvas_ref: NodeRef,
@@ -17,6 +285,11 @@ pub struct Scanner {
     is_scanning: bool,
     is_flashlight_on: bool,
     interval: Option<Interval>,
+    devices: Vec<CameraInfo>,
+    selected_device: Option<String>,
+    has_torch: bool,
+    zoom_range: Option<ZoomRange>,
+    zoom: f64,
 }
 
 pub enum ScannerMessage {
@@ -27,6 +300,13 @@ pub enum ScannerMessage {
     CloseScanner,
     ToggleFlashlight,
     VideoTimeUpdate,
+    DevicesEnumerated(Vec<CameraInfo>),
+    SelectDevice(String),
+    DecodeImage(File),
+    ImageDecoded(rxing::RXingResult),
+    FlashlightToggled(bool),
+    SetZoom(f64),
+    ZoomChanged(f64),
 }
 
 #[derive(Properties, PartialEq, Clone)]
@@ -37,6 +317,24 @@ pub struct ScannerProps {
     pub onerror: Callback<JsError>,
     #[prop_or_default]
     pub onclose: Callback<()>,
+    #[prop_or_default]
+    pub on_devices: Callback<Vec<CameraInfo>>,
+    #[prop_or_default]
+    pub device_id: Option<String>,
+    #[prop_or_default]
+    pub multi_scan: bool,
+    #[prop_or_default]
+    pub onscan_multi: Callback<Vec<rxing::RXingResult>>,
+    #[prop_or(true)]
+    pub try_harder: bool,
+    #[prop_or_default]
+    pub filter_image: bool,
+    #[prop_or_default]
+    pub formats: Vec<rxing::BarcodeFormat>,
+    #[prop_or_default]
+    pub pure_barcode: bool,
+    #[prop_or_default]
+    pub scan_region: Option<ScanRegion>,
     #[prop_or(500)]
     pub refresh_milliseconds: u32,
 }
@@ -78,6 +376,22 @@ impl Component for Scanner {
             is_scanning: false,
             is_flashlight_on: false,
             interval: None,
+            devices: Vec::new(),
+            selected_device: None,
+            has_torch: false,
+            zoom_range: None,
+            zoom: 1.0,
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            ctx.link().send_future(async {
+                match enumerate_cameras().await {
+                    Ok(cameras) => ScannerMessage::DevicesEnumerated(cameras),
+                    Err(e) => ScannerMessage::Error(js_error_from_value(e)),
+                }
+            });
         }
     }
 
@@ -94,17 +408,76 @@ impl Component for Scanner {
                     <button onclick={toggle_scanner} title="Start Scanner" class="start-scanner">
                         <i class="fas fa-qrcode"></i>
                     </button>
+                    <input
+                        type="file"
+                        accept="image/*"
+                        class="scan-from-file"
+                        title="Scan from an image"
+                        onchange={ctx.link().callback(|e: Event| {
+                            let input: HtmlInputElement = e.target_unchecked_into();
+                            match input.files().and_then(|files| files.get(0)) {
+                                Some(file) => ScannerMessage::DecodeImage(file),
+                                None => ScannerMessage::Error(JsError::from(js_sys::Error::new(
+                                    "No file selected",
+                                ))),
+                            }
+                        })}
+                    />
                 }
+                <canvas ref={&self.canvas_ref} width={video_width.to_string()} height={video_height.to_string()} style="display: none;"></canvas>
             // Modal for the scanner
             if self.is_scanning {
                 <div class="active-scanner-ui">
                     <div class="active-scanner-ui-content">
-                    <button class="toggle-flashlight" onclick={&toggle_flashlight} title="Turn on/off flashlight">
-                        <i class="fas fa-lightbulb"></i>
-                    </button> // Add this line
+                    if self.has_torch {
+                        <button class="toggle-flashlight" onclick={&toggle_flashlight} title="Turn on/off flashlight">
+                            <i class="fas fa-lightbulb"></i>
+                        </button>
+                    }
                         <button class="close" onclick={&close_scanner}>{ "×" }</button>
+                        if let Some(zoom_range) = &self.zoom_range {
+                            <input
+                                type="range"
+                                class="zoom-slider"
+                                title="Zoom"
+                                min={zoom_range.min.to_string()}
+                                max={zoom_range.max.to_string()}
+                                step={zoom_range.step.to_string()}
+                                value={self.zoom.to_string()}
+                                oninput={ctx.link().callback(|e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    ScannerMessage::SetZoom(input.value().parse().unwrap_or(1.0))
+                                })}
+                            />
+                        }
+                        if self.devices.len() > 1 {
+                            <select class="camera-select" onchange={ctx.link().callback(|e: Event| {
+                                let select: HtmlSelectElement = e.target_unchecked_into();
+                                ScannerMessage::SelectDevice(select.value())
+                            })}>
+                                { for self.devices.iter().map(|camera| html! {
+                                    <option value={camera.device_id.clone()}>{ &camera.label }</option>
+                                }) }
+                            </select>
+                        }
                         <video ref={&self.video_ref} autoPlay="true" ontimeupdate={time_update}/>
-                        <canvas ref={&self.canvas_ref} width={video_width.to_string()} height={video_height.to_string()} style="display: none;"></canvas>
+                        if let Some(region) = ctx.props().scan_region {
+                            {
+                                let (x, y, width, height) = region.clamped_normalized();
+                                html! {
+                                    <div
+                                        class="scan-region-overlay"
+                                        style={format!(
+                                            "left: {}%; top: {}%; width: {}%; height: {}%;",
+                                            x * 100.0,
+                                            y * 100.0,
+                                            width * 100.0,
+                                            height * 100.0
+                                        )}
+                                    ></div>
+                                }
+                            }
+                        }
                     </div>
                 </div>
                 }
@@ -132,6 +505,19 @@ impl Component for Scanner {
                     .expect("video should be an HtmlVideoElement");
 
                 video.set_src_object(self.stream.as_ref().clone());
+
+                if let Some(track) = self.stream.as_ref().and_then(|stream| {
+                    stream
+                        .get_video_tracks()
+                        .get(0)
+                        .dyn_into::<MediaStreamTrack>()
+                        .ok()
+                }) {
+                    let (has_torch, zoom_range) = read_camera_capabilities(&track);
+                    self.zoom = zoom_range.as_ref().map_or(1.0, |zoom_range| zoom_range.min);
+                    self.has_torch = has_torch;
+                    self.zoom_range = zoom_range;
+                }
                 true
             }
 
@@ -165,32 +551,68 @@ impl Component for Scanner {
                     }
                 }
 
-                let image_data =
-                    match context.get_image_data(0.0, 0.0, video_width as f64, video_height as f64)
-                    {
-                        Ok(image_data) => image_data,
-                        Err(error) => {
-                            log::error!("{:?}", error);
-                            return true;
-                        }
-                    };
+                let (crop_x, crop_y, crop_width, crop_height) = ctx
+                    .props()
+                    .scan_region
+                    .map(|region| region.to_pixel_rect(video_width, video_height))
+                    .unwrap_or((0.0, 0.0, video_width as f64, video_height as f64));
 
-                let decode_result = decode_barcode(
-                    convert_js_image_to_luma(image_data.data().as_ref()),
-                    image_data.width(),
-                    image_data.height(),
-                    Some(true),
-                    Some(false),
-                );
-                match decode_result {
-                    Ok(s) => {
-                        ctx.props().onscan.emit(s);
-                        ctx.link().send_message(ScannerMessage::CloseScanner);
+                let image_data = match context
+                    .get_image_data(crop_x, crop_y, crop_width, crop_height)
+                {
+                    Ok(image_data) => image_data,
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        return true;
                     }
-                    Err(e) => {
-                        ctx.link().send_message(ScannerMessage::Error(JsError::from(
-                            js_sys::Error::new(e.to_string().as_str()),
-                        )));
+                };
+
+                let try_harder = Some(ctx.props().try_harder);
+                let filter_image = Some(ctx.props().filter_image);
+                let formats = &ctx.props().formats;
+                let pure_barcode = Some(ctx.props().pure_barcode);
+
+                if ctx.props().multi_scan {
+                    let decode_result = decode_barcodes(
+                        convert_js_image_to_luma(image_data.data().as_ref()),
+                        image_data.width(),
+                        image_data.height(),
+                        try_harder,
+                        filter_image,
+                        formats,
+                        pure_barcode,
+                    );
+                    match decode_result {
+                        Ok(results) if !results.is_empty() => {
+                            ctx.props().onscan_multi.emit(results);
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            ctx.link().send_message(ScannerMessage::Error(JsError::from(
+                                js_sys::Error::new(e.to_string().as_str()),
+                            )));
+                        }
+                    }
+                } else {
+                    let decode_result = decode_barcode(
+                        convert_js_image_to_luma(image_data.data().as_ref()),
+                        image_data.width(),
+                        image_data.height(),
+                        try_harder,
+                        filter_image,
+                        formats,
+                        pure_barcode,
+                    );
+                    match decode_result {
+                        Ok(s) => {
+                            ctx.props().onscan.emit(s);
+                            ctx.link().send_message(ScannerMessage::CloseScanner);
+                        }
+                        Err(e) => {
+                            ctx.link().send_message(ScannerMessage::Error(JsError::from(
+                                js_sys::Error::new(e.to_string().as_str()),
+                            )));
+                        }
                     }
                 }
                 true
@@ -199,42 +621,70 @@ impl Component for Scanner {
                 ctx.props().onerror.emit(e);
                 true
             }
-            ScannerMessage::ToggleScanner => {
-                ctx.link().send_future(async {
-                    let mut constraints = MediaStreamConstraints::new();
-                    let mut video_constraints = MediaTrackConstraints::new();
-
-                    let advanced_constraints = js_sys::Array::new();
-                    let torch_constraint = js_sys::Object::new();
-                    js_sys::Reflect::set(
-                        &torch_constraint,
-                        &JsValue::from_str("torch"),
-                        &JsValue::from_bool(false),
-                    )
-                    .unwrap();
-                    advanced_constraints.push(&torch_constraint);
-                    video_constraints.advanced(&advanced_constraints);
-
-                    video_constraints
-                        .facing_mode(&VideoFacingModeEnum::Environment.into())
-                        .frame_rate(&10.into());
-
-                    constraints.video(&video_constraints);
-                    match window().navigator().media_devices() {
-                        Ok(devs) => match devs.get_user_media_with_constraints(&constraints) {
-                            Ok(promise) => {
-                                match wasm_bindgen_futures::JsFuture::from(promise).await {
-                                    Ok(stream) => {
-                                        ScannerMessage::ReceivedStream(stream.unchecked_into())
-                                    }
-                                    Err(e) => ScannerMessage::Error(JsError::try_from(e).unwrap()),
-                                }
+            ScannerMessage::DevicesEnumerated(devices) => {
+                ctx.props().on_devices.emit(devices.clone());
+                self.devices = devices;
+                true
+            }
+            ScannerMessage::SelectDevice(device_id) => {
+                self.selected_device = Some(device_id.clone());
+                if self.is_scanning {
+                    if let Some(stream) = self.stream.take() {
+                        for track in stream.get_tracks().iter() {
+                            if let Ok(track) = track.dyn_into::<MediaStreamTrack>() {
+                                track.stop();
                             }
-                            Err(e) => ScannerMessage::Error(JsError::try_from(e).unwrap()),
-                        },
-                        Err(e) => ScannerMessage::Error(JsError::try_from(e).unwrap()),
+                        }
+                    }
+                    self.has_torch = false;
+                    self.zoom_range = None;
+                    self.zoom = 1.0;
+                    ctx.link().send_future(start_camera(Some(device_id)));
+                }
+                true
+            }
+            ScannerMessage::DecodeImage(file) => {
+                let canvas = self.canvas_ref.cast::<HtmlCanvasElement>();
+                let try_harder = Some(ctx.props().try_harder);
+                let filter_image = Some(ctx.props().filter_image);
+                let formats = ctx.props().formats.clone();
+                let pure_barcode = Some(ctx.props().pure_barcode);
+                ctx.link().send_future(async move {
+                    let canvas = match canvas {
+                        Some(canvas) => canvas,
+                        None => {
+                            return ScannerMessage::Error(JsError::from(js_sys::Error::new(
+                                "canvas should be an HtmlCanvasElement",
+                            )));
+                        }
+                    };
+                    match decode_image_file(
+                        file,
+                        canvas,
+                        try_harder,
+                        filter_image,
+                        formats,
+                        pure_barcode,
+                    )
+                    .await
+                    {
+                        Ok(result) => ScannerMessage::ImageDecoded(result),
+                        Err(e) => ScannerMessage::Error(e),
                     }
                 });
+                false
+            }
+            ScannerMessage::ImageDecoded(result) => {
+                ctx.props().onscan.emit(result);
+                true
+            }
+            ScannerMessage::ToggleScanner => {
+                let device_id = ctx
+                    .props()
+                    .device_id
+                    .clone()
+                    .or_else(|| self.selected_device.clone());
+                ctx.link().send_future(start_camera(device_id));
                 self.is_scanning = !self.is_scanning;
                 true
             }
@@ -251,6 +701,9 @@ impl Component for Scanner {
                 self.is_scanning = false;
                 self.stream = None;
                 self.is_flashlight_on = false;
+                self.has_torch = false;
+                self.zoom_range = None;
+                self.zoom = 1.0;
                 if let Some(interval) = self.interval.take() {
                     interval.cancel();
                 }
@@ -258,33 +711,91 @@ impl Component for Scanner {
                 true
             }
             ScannerMessage::ToggleFlashlight => {
-                if let Some(stream) = &self.stream {
-                    let track = stream
-                        .get_video_tracks()
-                        .get(0)
-                        .dyn_into::<MediaStreamTrack>();
-                    let constraints = js_sys::Object::new();
-                    js_sys::Reflect::set(
-                        &constraints,
-                        &JsValue::from_str("torch"),
-                        &JsValue::from_bool(!self.is_flashlight_on),
-                    )
-                    .unwrap();
-                    let advanced_constraints = js_sys::Array::new();
-                    advanced_constraints.push(&constraints);
-                    let mut video_constraints = MediaTrackConstraints::new();
-                    video_constraints
-                        .advanced(&advanced_constraints)
-                        .facing_mode(&VideoFacingModeEnum::Environment.into())
-                        .frame_rate(&20.into());
-                    let _ = track
-                        .expect("Cannot apply constrait")
-                        .apply_constraints_with_constraints(&video_constraints)
-                        .unwrap();
-                    self.is_flashlight_on = !self.is_flashlight_on;
-                }
+                let Some(stream) = self.stream.clone() else {
+                    return false;
+                };
+                let turn_on = !self.is_flashlight_on;
+                ctx.link().send_future(async move {
+                    apply_advanced_constraint(&stream, "torch", JsValue::from_bool(turn_on))
+                        .await
+                        .map_or_else(
+                            |e| ScannerMessage::Error(js_error_from_value(e)),
+                            |_| ScannerMessage::FlashlightToggled(turn_on),
+                        )
+                });
+                false
+            }
+            ScannerMessage::FlashlightToggled(is_on) => {
+                self.is_flashlight_on = is_on;
+                true
+            }
+            ScannerMessage::SetZoom(zoom) => {
+                let Some(stream) = self.stream.clone() else {
+                    return false;
+                };
+                ctx.link().send_future(async move {
+                    apply_advanced_constraint(&stream, "zoom", JsValue::from_f64(zoom))
+                        .await
+                        .map_or_else(
+                            |e| ScannerMessage::Error(js_error_from_value(e)),
+                            |_| ScannerMessage::ZoomChanged(zoom),
+                        )
+                });
+                false
+            }
+            ScannerMessage::ZoomChanged(zoom) => {
+                self.zoom = zoom;
                 true
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ScanRegion;
+
+    #[test]
+    fn to_pixel_rect_stays_within_frame_for_in_range_region() {
+        let region = ScanRegion::Normalized {
+            x: 0.25,
+            y: 0.25,
+            width: 0.5,
+            height: 0.5,
+        };
+        let (x, y, w, h) = region.to_pixel_rect(800, 600);
+        assert_eq!((x, y, w, h), (200.0, 150.0, 400.0, 300.0));
+    }
+
+    #[test]
+    fn to_pixel_rect_clamps_out_of_range_origin_and_size() {
+        let region = ScanRegion::Normalized {
+            x: 0.8,
+            y: -0.2,
+            width: 0.5,
+            height: 1.5,
+        };
+        let (x, y, w, h) = region.to_pixel_rect(800, 600);
+        assert!(x >= 0.0 && x <= 800.0);
+        assert!(y >= 0.0 && y <= 600.0);
+        assert!(x + w <= 800.0);
+        assert!(y + h <= 600.0);
+    }
+
+    #[test]
+    fn overlay_and_decode_rect_agree_for_out_of_range_region() {
+        let region = ScanRegion::Normalized {
+            x: 0.8,
+            y: 0.8,
+            width: 0.5,
+            height: 0.5,
+        };
+        let (video_width, video_height) = (800u32, 600u32);
+        let (ox, oy, ow, oh) = region.clamped_normalized();
+        let (dx, dy, dw, dh) = region.to_pixel_rect(video_width, video_height);
+        assert_eq!(dx, ox * video_width as f64);
+        assert_eq!(dy, oy * video_height as f64);
+        assert_eq!(dw, ow * video_width as f64);
+        assert_eq!(dh, oh * video_height as f64);
+    }
+}
@@ -2,14 +2,13 @@ use std::collections::HashMap;
 
 use rxing::{self, Exceptions};
 
-/// Decode a barcode from an array of 8bit luma data
-pub(crate) fn decode_barcode(
-    data: Vec<u8>,
-    width: u32,
-    height: u32,
+/// Build the `DecodingHintDictionary` shared by the single- and
+/// multi-detection entry points below.
+fn build_hints(
     try_harder: Option<bool>,
-    filter_image: Option<bool>,
-) -> Result<rxing::RXingResult, Exceptions> {
+    formats: &[rxing::BarcodeFormat],
+    pure_barcode: Option<bool>,
+) -> rxing::DecodingHintDictionary {
     let mut hints: rxing::DecodingHintDictionary = HashMap::new();
     if let Some(true) = try_harder {
         hints.insert(
@@ -18,6 +17,36 @@ pub(crate) fn decode_barcode(
         );
     }
 
+    if !formats.is_empty() {
+        hints.insert(
+            rxing::DecodeHintType::POSSIBLE_FORMATS,
+            rxing::DecodeHintValue::PossibleFormats(formats.iter().copied().collect()),
+        );
+    }
+
+    if let Some(true) = pure_barcode {
+        hints.insert(
+            rxing::DecodeHintType::PURE_BARCODE,
+            rxing::DecodeHintValue::PureBarcode(true),
+        );
+    }
+
+    hints
+}
+
+/// Decode a barcode from an array of 8bit luma data
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decode_barcode(
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    try_harder: Option<bool>,
+    filter_image: Option<bool>,
+    formats: &[rxing::BarcodeFormat],
+    pure_barcode: Option<bool>,
+) -> Result<rxing::RXingResult, Exceptions> {
+    let mut hints = build_hints(try_harder, formats, pure_barcode);
+
     let detection_function = if matches!(filter_image, Some(true)) {
         rxing::helpers::detect_in_luma_filtered_with_hints
     } else {
@@ -27,6 +56,29 @@ pub(crate) fn decode_barcode(
     detection_function(data, width, height, None, &mut hints)
 }
 
+/// Decode every barcode present in an array of 8bit luma data, instead of
+/// stopping at the first match.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decode_barcodes(
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    try_harder: Option<bool>,
+    filter_image: Option<bool>,
+    formats: &[rxing::BarcodeFormat],
+    pure_barcode: Option<bool>,
+) -> Result<Vec<rxing::RXingResult>, Exceptions> {
+    let mut hints = build_hints(try_harder, formats, pure_barcode);
+
+    let detection_function = if matches!(filter_image, Some(true)) {
+        rxing::helpers::detect_multiple_in_luma_filtered_with_hints
+    } else {
+        rxing::helpers::detect_multiple_in_luma_with_hints
+    };
+
+    detection_function(data, width, height, &mut hints)
+}
+
 /// Convert a javascript image context's data into luma 8.
 ///
 /// Data for this function can be found from any canvas object